@@ -4,15 +4,30 @@
 
 use std::sync::Arc;
 
+use common_datavalues::DataField;
 use common_datavalues::DataSchemaRef;
+use common_datavalues::DataSchemaRefExt;
+use common_datavalues::DataType;
 
 use crate::{PlanNode, PlannerResult};
 
+// Like `Syntax`/`Graph`/`Pipeline`, `Json` and `Analyze` only select a rendering mode and a
+// result schema here; the explain interpreter that owns plan execution is what walks
+// `input` and actually produces the rows (rendered text for the first three, a
+// structured document for `Json`, and that document annotated with observed rows/elapsed
+// time per node for `Analyze`). That interpreter isn't part of this crate, so this plan
+// node can't do more than declare the shape of what it expects back.
 #[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
 pub enum DfExplainType {
     Syntax,
     Graph,
     Pipeline,
+    /// Renders the plan tree as a structured, machine-readable JSON document (node type,
+    /// schema, children, estimated cardinality) instead of text.
+    Json,
+    /// Like `Json`, but first executes the wrapped `input` plan and annotates the tree
+    /// with the actual rows produced and elapsed time observed for each node.
+    Analyze,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
@@ -22,8 +37,19 @@ pub struct ExplainPlan {
 }
 
 impl ExplainPlan {
+    /// Result schema for this explain mode. `Json` and `Analyze` both report their result
+    /// as a single `explain` string column, since the document built during execution is
+    /// returned serialized; the other modes pass the wrapped plan's own schema through,
+    /// because their interpreters don't change row shape, just formatting.
     pub fn schema(&self) -> DataSchemaRef {
-        self.input.schema()
+        match self.typ {
+            DfExplainType::Json | DfExplainType::Analyze => {
+                DataSchemaRefExt::create(vec![DataField::new("explain", DataType::String, false)])
+            }
+            DfExplainType::Syntax | DfExplainType::Graph | DfExplainType::Pipeline => {
+                self.input.schema()
+            }
+        }
     }
 
     pub fn input(&self) -> Arc<PlanNode> {
@@ -0,0 +1,466 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use common_datablocks::DataBlock;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataType;
+use common_datavalues::TypeDeserializer;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_exception::ToErrorCode;
+use common_io::prelude::FormatSettings;
+use futures::AsyncRead;
+use futures::AsyncReadExt;
+
+use crate::Source;
+
+/// The single byte Confluent's wire format prefixes every record with, ahead of the
+/// 4-byte big-endian schema id.
+const CONFLUENT_MAGIC_BYTE: u8 = 0x00;
+
+/// Bridges `&mut R`'s `AsyncRead` to `std::io::Read` so `avro_rs::from_avro_datum` — a
+/// synchronous decoder that knows how many bytes a record takes only by parsing it
+/// against the writer schema — can read an Avro body directly off the source, the same
+/// way NDJsonSource bridges its reader for `JsonLayout::Array`. The reader is polled with a
+/// no-op waker until it is ready rather than through `block_on`, so this also works when
+/// the caller is itself running inside a futures executor; a reader that stays pending
+/// keeps the calling thread spinning.
+struct BlockingReader<'a, R> {
+    inner: &'a mut R,
+}
+
+impl<R> std::io::Read for BlockingReader<'_, R>
+where R: AsyncRead + Unpin + Send
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut cx = std::task::Context::from_waker(futures::task::noop_waker_ref());
+        loop {
+            match AsyncRead::poll_read(std::pin::Pin::new(&mut *self.inner), &mut cx, buf) {
+                std::task::Poll::Ready(result) => return result,
+                std::task::Poll::Pending => std::thread::yield_now(),
+            }
+        }
+    }
+}
+
+/// Resolves (and is expected to cache on the caller's side) the writer `avro_rs::Schema`
+/// registered under `schema_id`. Implementations typically talk to a Confluent-compatible
+/// schema registry over HTTP; tests can stub this with an in-memory map.
+#[async_trait]
+pub trait SchemaRegistryClient: Send + Sync {
+    async fn get_schema(&self, schema_id: u32) -> Result<Arc<avro_rs::Schema>>;
+}
+
+#[derive(Clone)]
+pub struct AvroSourceBuilder {
+    schema: DataSchemaRef,
+    block_size: usize,
+    size_limit: usize,
+    format: FormatSettings,
+    /// Maps a writer-schema field name to the target column name, for schemas that have
+    /// renamed a field since the column was first ingested.
+    field_renames: HashMap<String, String>,
+}
+
+impl AvroSourceBuilder {
+    pub fn create(schema: DataSchemaRef, format: FormatSettings) -> Self {
+        AvroSourceBuilder {
+            schema,
+            block_size: 10000,
+            size_limit: usize::MAX,
+            format,
+            field_renames: HashMap::new(),
+        }
+    }
+
+    pub fn block_size(&mut self, block_size: usize) -> &mut Self {
+        self.block_size = block_size;
+        self
+    }
+
+    pub fn size_limit(&mut self, size_limit: usize) -> &mut Self {
+        self.size_limit = size_limit;
+        self
+    }
+
+    /// Register that the writer schema's `from` field should be read into the target
+    /// column `to`, so ingest survives a field rename upstream.
+    pub fn rename_field(&mut self, from: impl Into<String>, to: impl Into<String>) -> &mut Self {
+        self.field_renames.insert(from.into(), to.into());
+        self
+    }
+
+    pub fn build<R>(
+        &self,
+        reader: R,
+        registry: Arc<dyn SchemaRegistryClient>,
+    ) -> Result<AvroSource<R>>
+    where
+        R: AsyncRead + Unpin + Send,
+    {
+        AvroSource::try_create(self.clone(), reader, registry)
+    }
+}
+
+pub struct AvroSource<R> {
+    builder: AvroSourceBuilder,
+    reader: R,
+    registry: Arc<dyn SchemaRegistryClient>,
+    schema_cache: HashMap<u32, Arc<avro_rs::Schema>>,
+    rows: usize,
+}
+
+impl<R> AvroSource<R>
+where R: AsyncRead + Unpin + Send
+{
+    fn try_create(
+        builder: AvroSourceBuilder,
+        reader: R,
+        registry: Arc<dyn SchemaRegistryClient>,
+    ) -> Result<Self> {
+        Ok(Self {
+            builder,
+            reader,
+            registry,
+            schema_cache: HashMap::new(),
+            rows: 0,
+        })
+    }
+
+    /// Reads one Confluent-framed record directly off the wire: a `0x00` magic byte, a
+    /// 4-byte big-endian schema id, then the Avro binary body with no length envelope of
+    /// its own — real producers (Confluent's `KafkaAvroSerializer`, Debezium, ...) write
+    /// exactly these bytes back-to-back, relying on `avro_rs::from_avro_datum` being
+    /// self-delimiting against the writer schema rather than on any framing length.
+    /// Returns `None` once the reader is exhausted between records.
+    async fn read_record(&mut self) -> Result<Option<avro_rs::types::Value>> {
+        let mut magic = [0u8; 1];
+        match self.reader.read_exact(&mut magic).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => {
+                return Err(ErrorCode::BadBytes(format!(
+                    "Parse Avro error at record {}: failed to read magic byte: {}",
+                    self.rows, e
+                )));
+            }
+        }
+        if magic[0] != CONFLUENT_MAGIC_BYTE {
+            return Err(ErrorCode::BadBytes(format!(
+                "Parse Avro error at record {}: missing Confluent magic byte",
+                self.rows
+            )));
+        }
+
+        let mut id_buf = [0u8; 4];
+        self.reader
+            .read_exact(&mut id_buf)
+            .await
+            .map_err_to_code(ErrorCode::BadBytes, || {
+                format!(
+                    "Parse Avro error at record {}: truncated schema id",
+                    self.rows
+                )
+            })?;
+        let schema_id = u32::from_be_bytes(id_buf);
+
+        let writer_schema = self.resolve_schema(schema_id).await?;
+
+        let mut blocking = BlockingReader {
+            inner: &mut self.reader,
+        };
+        let value = avro_rs::from_avro_datum(&writer_schema, &mut blocking, None).map_err(|e| {
+            ErrorCode::BadBytes(format!(
+                "Parse Avro error at record {}: schema {}: {}",
+                self.rows, schema_id, e
+            ))
+        })?;
+
+        Ok(Some(value))
+    }
+
+    async fn resolve_schema(&mut self, schema_id: u32) -> Result<Arc<avro_rs::Schema>> {
+        if let Some(schema) = self.schema_cache.get(&schema_id) {
+            return Ok(schema.clone());
+        }
+        let schema = self.registry.get_schema(schema_id).await?;
+        self.schema_cache.insert(schema_id, schema.clone());
+        Ok(schema)
+    }
+
+    /// Looks up `field` in a decoded Avro record, falling back to any configured rename
+    /// and finally to `None` when the writer schema has dropped the field altogether.
+    fn field_value<'v>(
+        &self,
+        fields: &'v [(String, avro_rs::types::Value)],
+        name: &str,
+    ) -> Option<&'v avro_rs::types::Value> {
+        fields
+            .iter()
+            .find(|(k, _)| k == name)
+            .or_else(|| {
+                self.builder
+                    .field_renames
+                    .iter()
+                    .find(|(_, to)| to.as_str() == name)
+                    .and_then(|(from, _)| fields.iter().find(|(k, _)| k == from))
+            })
+            .map(|(_, v)| v)
+    }
+}
+
+#[async_trait]
+impl<R> Source for AvroSource<R>
+where R: AsyncRead + Unpin + Send
+{
+    async fn read(&mut self) -> Result<Option<DataBlock>> {
+        if self.rows >= self.builder.size_limit {
+            return Ok(None);
+        }
+
+        let mut packs = self
+            .builder
+            .schema
+            .fields()
+            .iter()
+            .map(|f| f.data_type().create_deserializer(self.builder.block_size))
+            .collect::<Vec<_>>();
+
+        let field_names = self
+            .builder
+            .schema
+            .fields()
+            .iter()
+            .map(|f| f.name().to_string())
+            .collect::<Vec<_>>();
+
+        let mut rows = 0;
+
+        while let Some(value) = self.read_record().await? {
+            let record_fields = match value {
+                avro_rs::types::Value::Record(fields) => fields,
+                other => {
+                    return Err(ErrorCode::BadBytes(format!(
+                        "Parse Avro error at record {}: expected a record, got {:?}",
+                        self.rows, other
+                    )));
+                }
+            };
+
+            for (name, deser) in field_names.iter().zip(packs.iter_mut()) {
+                // Schema evolution: a field the target schema expects may be absent from
+                // the writer schema (renamed or dropped); deserialize a null rather than
+                // aborting the whole batch.
+                let value = self
+                    .field_value(&record_fields, name)
+                    .cloned()
+                    .unwrap_or(avro_rs::types::Value::Null);
+
+                // Route through the same `TypeDeserializer::de_json` entry point NDJsonSource
+                // uses, so column conversion logic isn't duplicated per source format.
+                let json_value: serde_json::Value = value.try_into().map_err(|e| {
+                    ErrorCode::BadBytes(format!(
+                        "error at row {} column {}: avro value is not representable as json: {}",
+                        rows, name, e
+                    ))
+                })?;
+
+                deser
+                    .de_json(&json_value, &self.builder.format)
+                    .map_err(|e| {
+                        ErrorCode::BadBytes(format!(
+                            "error at row {} column {}: err={}",
+                            rows,
+                            name,
+                            e.message(),
+                        ))
+                    })?;
+            }
+
+            rows += 1;
+            self.rows += 1;
+
+            if self.rows >= self.builder.size_limit {
+                break;
+            }
+            if rows >= self.builder.block_size {
+                break;
+            }
+        }
+
+        if rows == 0 {
+            return Ok(None);
+        }
+
+        let series = packs
+            .iter_mut()
+            .map(|deser| deser.finish_to_column())
+            .collect::<Vec<_>>();
+
+        Ok(Some(DataBlock::create(self.builder.schema.clone(), series)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_exception::Result;
+
+    use super::*;
+
+    /// Resolves a fixed, caller-supplied schema regardless of `schema_id`, so tests can
+    /// exercise `AvroSource` without standing up a real registry.
+    struct StubRegistry {
+        schema: Arc<avro_rs::Schema>,
+    }
+
+    #[async_trait]
+    impl SchemaRegistryClient for StubRegistry {
+        async fn get_schema(&self, _schema_id: u32) -> Result<Arc<avro_rs::Schema>> {
+            Ok(self.schema.clone())
+        }
+    }
+
+    /// Builds a genuine Confluent-framed record: magic byte + big-endian schema id +
+    /// Avro body, with no length envelope of any kind — this is exactly what
+    /// `KafkaAvroSerializer`/Debezium write, and what `read_record` must parse.
+    fn confluent_frame(schema_id: u32, body: &[u8]) -> Vec<u8> {
+        let mut frame = Vec::with_capacity(5 + body.len());
+        frame.push(CONFLUENT_MAGIC_BYTE);
+        frame.extend_from_slice(&schema_id.to_be_bytes());
+        frame.extend_from_slice(body);
+        frame
+    }
+
+    fn test_source(bytes: Vec<u8>) -> AvroSource<&'static [u8]> {
+        let schema = avro_rs::Schema::parse_str(
+            r#"{"type": "record", "name": "r", "fields": [{"name": "a", "type": "long"}]}"#,
+        )
+        .unwrap();
+        let builder = AvroSourceBuilder::create(
+            common_datavalues::DataSchemaRefExt::create(vec![]),
+            FormatSettings::default(),
+        );
+        let registry: Arc<dyn SchemaRegistryClient> = Arc::new(StubRegistry {
+            schema: Arc::new(schema),
+        });
+        AvroSource::try_create(builder, Box::leak(bytes.into_boxed_slice()), registry).unwrap()
+    }
+
+    #[test]
+    fn read_record_returns_none_at_eof() {
+        let mut source = test_source(vec![]);
+        let record = futures::executor::block_on(source.read_record()).unwrap();
+        assert!(record.is_none());
+    }
+
+    #[test]
+    fn read_record_rejects_truncated_schema_id() {
+        // The magic byte is present but only 2 of the 4 schema-id bytes follow it.
+        let mut source = test_source(vec![CONFLUENT_MAGIC_BYTE, 0x00, 0x01]);
+        let err = futures::executor::block_on(source.read_record()).unwrap_err();
+        assert!(err.message().contains("truncated schema id"));
+    }
+
+    #[test]
+    fn read_record_rejects_bad_magic_byte() {
+        let frame = confluent_frame(7, &[]);
+        let mut source = test_source(vec![0xFF, frame[1], frame[2], frame[3], frame[4]]);
+        let err = futures::executor::block_on(source.read_record()).unwrap_err();
+        assert!(err.message().contains("magic byte"));
+    }
+
+    #[test]
+    fn read_record_rejects_truncated_avro_body() {
+        // Magic byte and schema id are intact, but the Avro body behind them is empty,
+        // so decoding the `long` field against the writer schema fails partway through.
+        let frame = confluent_frame(1, &[]);
+        let mut source = test_source(frame);
+        let err = futures::executor::block_on(source.read_record()).unwrap_err();
+        assert!(err.message().contains("schema 1"));
+    }
+
+    #[test]
+    fn read_record_parses_valid_frame() {
+        let schema = avro_rs::Schema::parse_str(
+            r#"{"type": "record", "name": "r", "fields": [{"name": "a", "type": "long"}]}"#,
+        )
+        .unwrap();
+        let mut record = avro_rs::types::Record::new(&schema).unwrap();
+        record.put("a", 7i64);
+        let encoded = avro_rs::to_avro_datum(&schema, record).unwrap();
+        let frame = confluent_frame(42, &encoded);
+
+        let mut source = test_source(frame);
+        let value = futures::executor::block_on(source.read_record())
+            .unwrap()
+            .unwrap();
+        match value {
+            avro_rs::types::Value::Record(fields) => {
+                assert_eq!(fields, vec![("a".to_string(), avro_rs::types::Value::Long(7))]);
+            }
+            other => panic!("expected a record, got {:?}", other),
+        }
+    }
+
+    fn field_value_source() -> AvroSource<&'static [u8]> {
+        let mut builder = AvroSourceBuilder::create(
+            common_datavalues::DataSchemaRefExt::create(vec![]),
+            FormatSettings::default(),
+        );
+        builder.rename_field("old_name", "new_name");
+        let schema = avro_rs::Schema::parse_str(
+            r#"{"type": "record", "name": "r", "fields": [{"name": "a", "type": "long"}]}"#,
+        )
+        .unwrap();
+        let registry: Arc<dyn SchemaRegistryClient> = Arc::new(StubRegistry {
+            schema: Arc::new(schema),
+        });
+        AvroSource::try_create(builder, &[][..], registry).unwrap()
+    }
+
+    #[test]
+    fn field_value_finds_exact_match() {
+        let source = field_value_source();
+        let fields = vec![("new_name".to_string(), avro_rs::types::Value::Long(1))];
+        assert_eq!(
+            source.field_value(&fields, "new_name"),
+            Some(&avro_rs::types::Value::Long(1))
+        );
+    }
+
+    #[test]
+    fn field_value_falls_back_to_renamed_field() {
+        let source = field_value_source();
+        // Writer schema still uses the pre-rename field name.
+        let fields = vec![("old_name".to_string(), avro_rs::types::Value::Long(2))];
+        assert_eq!(
+            source.field_value(&fields, "new_name"),
+            Some(&avro_rs::types::Value::Long(2))
+        );
+    }
+
+    #[test]
+    fn field_value_returns_none_when_missing() {
+        let source = field_value_source();
+        let fields = vec![("unrelated".to_string(), avro_rs::types::Value::Long(3))];
+        assert_eq!(source.field_value(&fields, "new_name"), None);
+    }
+}
@@ -13,6 +13,9 @@
 // limitations under the License.
 
 use std::borrow::Cow;
+use std::cell::RefCell;
+use std::io::Read as StdRead;
+use std::rc::Rc;
 
 use async_trait::async_trait;
 use common_datablocks::DataBlock;
@@ -23,17 +26,48 @@ use common_exception::ErrorCode;
 use common_exception::Result;
 use common_exception::ToErrorCode;
 use common_io::prelude::FormatSettings;
+use futures::executor::block_on;
 use futures::AsyncBufRead;
 use futures::AsyncBufReadExt;
+use serde::Deserialize;
 
 use crate::Source;
 
-#[derive(Debug, Clone)]
+/// serde_json's own recursion guard rejects documents nested past 128 levels; we disable
+/// it and enforce `max_depth` ourselves so the limit is configurable per source.
+const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// How `NDJsonSource` reacts to a line that fails to parse or deserialize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NDJsonErrorPolicy {
+    /// Abort the scan on the first bad line (previous, and still default, behaviour).
+    Abort,
+    /// Skip every bad line, no matter how many are encountered.
+    SkipInvalid,
+    /// Skip bad lines until more than `n` have been rejected, then abort.
+    MaxErrors(usize),
+}
+
+/// How records are framed in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonLayout {
+    /// One compact JSON object per physical line (the original format). Each line is parsed
+    /// on its own, so a record spread over several lines is not accepted in this layout.
+    NdJson,
+    /// A single top-level JSON array of objects, optionally pretty-printed.
+    Array,
+}
+
+#[derive(Clone)]
 pub struct NDJsonSourceBuilder {
     schema: DataSchemaRef,
     block_size: usize,
     size_limit: usize,
     format: FormatSettings,
+    arbitrary_precision: bool,
+    max_depth: usize,
+    on_error: NDJsonErrorPolicy,
+    layout: JsonLayout,
 }
 
 impl NDJsonSourceBuilder {
@@ -43,6 +77,10 @@ impl NDJsonSourceBuilder {
             block_size: 10000,
             size_limit: usize::MAX,
             format,
+            arbitrary_precision: false,
+            max_depth: DEFAULT_MAX_DEPTH,
+            on_error: NDJsonErrorPolicy::Abort,
+            layout: JsonLayout::NdJson,
         }
     }
 
@@ -56,18 +94,287 @@ impl NDJsonSourceBuilder {
         self
     }
 
+    /// When enabled, numbers are kept in their original textual form (via serde_json's
+    /// `arbitrary_precision` feature) instead of being decoded through `f64`/`i64`, so
+    /// 64-bit integers near the limits and high-precision decimals round-trip exactly.
+    ///
+    /// Requires the `arbitrary_precision` Cargo feature to be enabled on this crate's
+    /// `serde_json` dependency (`serde_json = { version = "...", features =
+    /// ["arbitrary_precision"] }` in its `Cargo.toml`); without it `serde_json::Number::as_str`
+    /// isn't available and `Number` values are already float/int-lossy by the time they reach
+    /// this builder. This crate currently has no `Cargo.toml` of its own in this tree to carry
+    /// that feature flag - wiring it in is a prerequisite for this option to do anything.
+    pub fn arbitrary_precision(&mut self, arbitrary_precision: bool) -> &mut Self {
+        self.arbitrary_precision = arbitrary_precision;
+        self
+    }
+
+    /// Maximum nesting depth (objects/arrays) allowed in a single record, enforced in
+    /// place of serde_json's fixed 128-level recursion limit. Defaults to 128.
+    pub fn max_depth(&mut self, max_depth: usize) -> &mut Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Policy applied when a line fails to parse or deserialize. Defaults to `Abort`.
+    pub fn on_error(&mut self, on_error: NDJsonErrorPolicy) -> &mut Self {
+        self.on_error = on_error;
+        self
+    }
+
+    /// Whether the input is one record per line, or a single top-level JSON array.
+    /// Defaults to `JsonLayout::NdJson`.
+    pub fn layout(&mut self, layout: JsonLayout) -> &mut Self {
+        self.layout = layout;
+        self
+    }
+
     pub fn build<R>(&self, reader: R) -> Result<NDJsonSource<R>>
     where R: AsyncBufRead + Unpin + Send {
         NDJsonSource::try_create(self.clone(), reader, self.format.ident_case_sensitive)
     }
 }
 
+/// Bridges an `AsyncBufRead` to `std::io::Read` so serde_json's synchronous
+/// `StreamDeserializer` can drive it record-by-record in `JsonLayout::Array` mode. The reader
+/// is polled with a no-op waker until it is ready rather than through `block_on`, so this
+/// also works when the caller is itself running inside a futures executor; a reader that
+/// stays pending keeps the calling thread spinning.
+struct BlockingReader<R> {
+    inner: R,
+}
+
+impl<R> StdRead for BlockingReader<R>
+where R: AsyncBufRead + Unpin + Send
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut cx = std::task::Context::from_waker(futures::task::noop_waker_ref());
+        loop {
+            match futures::AsyncRead::poll_read(std::pin::Pin::new(&mut self.inner), &mut cx, buf) {
+                std::task::Poll::Ready(result) => return result,
+                std::task::Poll::Pending => std::thread::yield_now(),
+            }
+        }
+    }
+}
+
+/// Wraps a byte source and counts `{`/`[` nesting live, as bytes are pulled off the wire,
+/// failing the `read` call the instant `max_depth` would be exceeded. This has to run ahead
+/// of `serde_json::Value`'s own (recursive, native-stack-consuming) `Deserialize` impl:
+/// checking depth only after a `Value` has already been fully built means a malicious,
+/// deeply nested line overflows the real stack while `Value::deserialize` is still
+/// recursing, long before any post-hoc check gets a chance to run. Intercepting at the byte
+/// level means the `(max_depth + 1)`-th opening bracket never reaches the parser, so it
+/// never recurses past `max_depth` call frames to begin with.
+struct DepthLimitReader<T> {
+    inner: T,
+    depth: usize,
+    max_depth: usize,
+    in_string: bool,
+    escaped: bool,
+    /// When set, every byte handed back to the caller is also appended here, so a
+    /// `JsonLayout::Array` reader can recover the raw text of a record after the fact
+    /// (see `ArrayReading` and its use in `next_value`) without buffering the whole
+    /// stream up front.
+    tee: Option<Rc<RefCell<Vec<u8>>>>,
+    /// At most one byte, already pulled from `inner` (and tee'd) by `skip_to_value_start`
+    /// or `resync_to_next_record` while peeking ahead for the next record's boundary, that
+    /// hasn't been handed to a reader yet. Replayed by `read` before pulling anything new.
+    pending: Option<u8>,
+}
+
+impl<T> DepthLimitReader<T> {
+    fn new(inner: T, max_depth: usize) -> Self {
+        DepthLimitReader {
+            inner,
+            depth: 0,
+            max_depth,
+            in_string: false,
+            escaped: false,
+            tee: None,
+            pending: None,
+        }
+    }
+
+    fn with_tee(inner: T, max_depth: usize, tee: Rc<RefCell<Vec<u8>>>) -> Self {
+        DepthLimitReader {
+            tee: Some(tee),
+            ..Self::new(inner, max_depth)
+        }
+    }
+}
+
+impl<T: std::io::Read> DepthLimitReader<T> {
+    /// Reads and tees exactly one byte straight from `inner`, bypassing `depth`/`in_string`
+    /// tracking and `max_depth` enforcement. Used by the boundary-scanning helpers below,
+    /// which track nesting themselves on their own terms (and, in `resync_to_next_record`'s
+    /// case, deliberately don't want `max_depth` re-triggered while unwinding a structure
+    /// that's already past it).
+    fn raw_read_one(&mut self, byte: &mut [u8; 1]) -> std::io::Result<usize> {
+        let n = self.inner.read(byte)?;
+        if n == 1 {
+            if let Some(tee) = &self.tee {
+                tee.borrow_mut().push(byte[0]);
+            }
+        }
+        Ok(n)
+    }
+
+    /// Updates `depth`/`in_string`/`escaped` for one byte without enforcing `max_depth`.
+    fn scan_unchecked(&mut self, b: u8) {
+        if self.in_string {
+            if self.escaped {
+                self.escaped = false;
+            } else if b == b'\\' {
+                self.escaped = true;
+            } else if b == b'"' {
+                self.in_string = false;
+            }
+            return;
+        }
+        match b {
+            b'"' => self.in_string = true,
+            b'[' | b'{' => self.depth += 1,
+            b']' | b'}' => self.depth = self.depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    /// Skips whitespace and, at most once, a leading separator (the array's opening `[`
+    /// on the very first record, or the `,` between later ones), landing on the first byte
+    /// of the next value. That byte is stashed in `pending` so the next fresh `Deserializer`
+    /// reads it as part of the record rather than losing it to this lookahead. Returns
+    /// `false` once `]` or EOF shows there's nothing left to parse.
+    fn skip_to_value_start(&mut self) -> std::io::Result<bool> {
+        let mut byte = [0u8; 1];
+        let mut skipped_separator = false;
+        loop {
+            if self.raw_read_one(&mut byte)? == 0 {
+                return Ok(false);
+            }
+            match byte[0] {
+                b if b.is_ascii_whitespace() => continue,
+                b'[' | b',' if !skipped_separator => skipped_separator = true,
+                b']' => return Ok(false),
+                other => {
+                    self.pending = Some(other);
+                    return Ok(true);
+                }
+            }
+        }
+    }
+
+    /// Called after a record-level failure (a parse error, or our own `max_depth`
+    /// violation) to walk the reader past whatever was left of the broken record — unclosed
+    /// objects/arrays, mid-string, or just a malformed token's leftover characters — and on
+    /// to the next array element, so one bad record doesn't take the rest of the array down
+    /// with it. Once nesting is back to zero and a `,` is found, hands off to
+    /// `skip_to_value_start` for the usual "land on the next value" step.
+    fn resync_to_next_record(&mut self) -> std::io::Result<bool> {
+        let mut byte = [0u8; 1];
+        loop {
+            if self.depth == 0 && !self.in_string {
+                if self.raw_read_one(&mut byte)? == 0 {
+                    return Ok(false);
+                }
+                match byte[0] {
+                    b if b.is_ascii_whitespace() => continue,
+                    b',' => return self.skip_to_value_start(),
+                    b']' => return Ok(false),
+                    other => self.scan_unchecked(other),
+                }
+            } else {
+                if self.raw_read_one(&mut byte)? == 0 {
+                    return Ok(false);
+                }
+                self.scan_unchecked(byte[0]);
+            }
+        }
+    }
+}
+
+impl<T: std::io::Read> std::io::Read for DepthLimitReader<T> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = if let Some(b) = self.pending.take() {
+            buf[0] = b;
+            1
+        } else {
+            let n = self.inner.read(buf)?;
+            if let Some(tee) = &self.tee {
+                tee.borrow_mut().extend_from_slice(&buf[..n]);
+            }
+            n
+        };
+
+        for &b in &buf[..n] {
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if b == b'\\' {
+                    self.escaped = true;
+                } else if b == b'"' {
+                    self.in_string = false;
+                }
+                continue;
+            }
+
+            match b {
+                b'"' => self.in_string = true,
+                b'[' | b'{' => {
+                    self.depth += 1;
+                    if self.depth > self.max_depth {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            format!(
+                                "nesting depth {} exceeds the configured max_depth {}",
+                                self.depth, self.max_depth
+                            ),
+                        ));
+                    }
+                }
+                b']' | b'}' => self.depth = self.depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+
+        Ok(n)
+    }
+}
+
+/// Drives a `JsonLayout::Array` stream. Each record is parsed with its own fresh
+/// `serde_json::Deserializer` over `reader` rather than one long-lived `StreamDeserializer`
+/// for the whole array: `StreamDeserializer` latches into a failed state after its first
+/// `Err` and returns `None` (indistinguishable from a clean end-of-array) from then on, which
+/// would silently truncate ingestion the moment `on_error` is anything but `Abort`. Per-record
+/// parsing, plus `DepthLimitReader::resync_to_next_record` to walk past whatever a failed
+/// record left open, keeps one bad element from taking the rest of the array down with it.
+///
+/// `tee`/`tee_base` mirror the NdJson branch's line buffer: they let each record's raw source
+/// text be recovered from the underlying `DepthLimitReader`'s tee for dead-letter capture,
+/// without buffering the whole array up front.
+struct ArrayReading<R> {
+    reader: DepthLimitReader<BlockingReader<R>>,
+    tee: Rc<RefCell<Vec<u8>>>,
+    /// Offset, within the whole stream, that byte 0 of `tee` currently corresponds to.
+    tee_base: usize,
+    /// Set once `]` or EOF has been reached, so later calls short-circuit to `Ok(None)`
+    /// instead of trying to read past the end of the array.
+    done: bool,
+}
+
+enum Reading<R> {
+    NdJson(R),
+    Array(ArrayReading<R>),
+}
+
 pub struct NDJsonSource<R> {
     builder: NDJsonSourceBuilder,
-    reader: R,
+    reading: Reading<R>,
     rows: usize,
     buffer: String,
     ident_case_sensitive: bool,
+    rejected_rows: usize,
+    dead_letters: Vec<(String, String)>,
 }
 
 impl<R> NDJsonSource<R>
@@ -78,14 +385,138 @@ where R: AsyncBufRead + Unpin + Send
         reader: R,
         ident_case_sensitive: bool,
     ) -> Result<Self> {
+        let reading = match builder.layout {
+            JsonLayout::NdJson => Reading::NdJson(reader),
+            JsonLayout::Array => {
+                let blocking = BlockingReader { inner: reader };
+                let tee = Rc::new(RefCell::new(Vec::new()));
+                let reader = DepthLimitReader::with_tee(blocking, builder.max_depth, tee.clone());
+                Reading::Array(ArrayReading {
+                    reader,
+                    tee,
+                    tee_base: 0,
+                    done: false,
+                })
+            }
+        };
         Ok(Self {
             builder,
-            reader,
+            reading,
             rows: 0,
             buffer: String::new(),
             ident_case_sensitive,
+            rejected_rows: 0,
+            dead_letters: vec![],
         })
     }
+
+    /// Number of lines rejected so far under a `SkipInvalid`/`MaxErrors` policy.
+    pub fn rejected_rows(&self) -> usize {
+        self.rejected_rows
+    }
+
+    /// The offending line paired with its error message, for every rejected row so far.
+    pub fn dead_letters(&self) -> &[(String, String)] {
+        &self.dead_letters
+    }
+
+    /// Pulls the next record as a parsed `Value`. Nesting depth is enforced live by
+    /// `DepthLimitReader` as bytes are read, not after the fact, so a maliciously deep
+    /// record is rejected before it can recurse past `max_depth` native stack frames.
+    async fn next_value(&mut self) -> Result<Option<serde_json::Value>> {
+        match &mut self.reading {
+            Reading::NdJson(reader) => loop {
+                self.buffer.clear();
+                let read = reader
+                    .read_line(&mut self.buffer)
+                    .await
+                    .map_err_to_code(ErrorCode::BadBytes, || {
+                        format!("Parse NDJson error at line {}", self.rows)
+                    })?;
+                if read == 0 {
+                    return Ok(None);
+                }
+                if self.buffer.trim().is_empty() {
+                    continue;
+                }
+                let depth_limited = DepthLimitReader::new(self.buffer.as_bytes(), self.builder.max_depth);
+                let mut de = serde_json::Deserializer::from_reader(depth_limited);
+                de.disable_recursion_limit();
+                let line = self.rows;
+                let value = serde_json::Value::deserialize(&mut de).map_err(|e| {
+                    ErrorCode::BadBytes(format!("Parse NDJson error at line {}: {}", line, e))
+                })?;
+                return Ok(Some(value));
+            },
+            Reading::Array(array) => {
+                if array.done {
+                    return Ok(None);
+                }
+
+                // A failed record's `resync_to_next_record` may already have landed on this
+                // record's first byte and stashed it in `pending`.
+                let has_value = if array.reader.pending.is_some() {
+                    true
+                } else {
+                    array.reader.skip_to_value_start().map_err(|e| {
+                        ErrorCode::BadBytes(format!(
+                            "Parse Json array error at record {}: {}",
+                            self.rows, e
+                        ))
+                    })?
+                };
+                if !has_value {
+                    array.done = true;
+                    return Ok(None);
+                }
+
+                // The byte `skip_to_value_start` just peeked is already in the tee, stashed
+                // in `reader.pending` for the fresh `Deserializer` below to read as this
+                // record's first byte — so it belongs to this record, not the separator.
+                let record_start = array.tee_base + array.tee.borrow().len() - 1;
+
+                let mut de = serde_json::Deserializer::from_reader(&mut array.reader);
+                de.disable_recursion_limit();
+                let result = serde_json::Value::deserialize(&mut de);
+
+                if result.is_err() {
+                    let has_more = array.reader.resync_to_next_record().map_err(|e| {
+                        ErrorCode::BadBytes(format!(
+                            "Parse Json array error at record {}: {}",
+                            self.rows, e
+                        ))
+                    })?;
+                    if !has_more {
+                        array.done = true;
+                    }
+                }
+
+                // Recover this record's raw text from the tee buffer so `self.buffer` (what
+                // dead-letter capture logs) reflects the actual source, the same as the
+                // NdJson branch's line buffer, rather than always being empty.
+                // A byte left in `pending` by the resync belongs to the next record, so it
+                // stays in the tee.
+                let record_end = array.tee_base + array.tee.borrow().len()
+                    - array.reader.pending.is_some() as usize;
+                let end_in_tee = record_end - array.tee_base;
+                let start_in_tee = record_start - array.tee_base;
+                let raw = array.tee.borrow()[start_in_tee..end_in_tee].to_vec();
+                self.buffer = String::from_utf8_lossy(&raw).trim().to_string();
+
+                // Drain what we've already sliced out so the tee buffer doesn't grow to
+                // hold the whole array.
+                array.tee.borrow_mut().drain(..end_in_tee);
+                array.tee_base = record_end;
+
+                result.map(Some).map_err(|e| {
+                    ErrorCode::BadBytes(format!(
+                        "Parse Json array error at record {}: {}",
+                        self.rows, e
+                    ))
+                })
+            }
+        }
+    }
 }
 
 fn maybe_truncated(s: &str, limit: usize) -> Cow<'_, str> {
@@ -130,51 +561,91 @@ where R: AsyncBufRead + Unpin + Send
         let mut rows = 0;
 
         loop {
-            self.buffer.clear();
-
-            if self
-                .reader
-                .read_line(&mut self.buffer)
-                .await
-                .map_err_to_code(ErrorCode::BadBytes, || {
-                    format!("Parse NDJson error at line {}", self.rows)
-                })?
-                == 0
-            {
-                break;
-            }
+            let arbitrary_precision = self.builder.arbitrary_precision;
+            let ident_case_sensitive = self.ident_case_sensitive;
 
-            if self.buffer.trim().is_empty() {
-                continue;
-            }
+            let next = self.next_value().await;
 
-            let mut json: serde_json::Value = serde_json::from_reader(self.buffer.as_bytes())?;
+            let line_result: Result<Option<()>> = (|| {
+                let mut json = match next? {
+                    Some(json) => json,
+                    None => return Ok(None),
+                };
 
-            // if it's not case_sensitive, we convert to lowercase
-            if !self.ident_case_sensitive {
-                if let serde_json::Value::Object(x) = json {
-                    let y = x.into_iter().map(|(k, v)| (k.to_lowercase(), v)).collect();
-                    json = serde_json::Value::Object(y);
+                // if it's not case_sensitive, we convert to lowercase
+                if !ident_case_sensitive {
+                    if let serde_json::Value::Object(x) = json {
+                        let y = x.into_iter().map(|(k, v)| (k.to_lowercase(), v)).collect();
+                        json = serde_json::Value::Object(y);
+                    }
                 }
-            }
 
-            for ((name, type_name), deser) in fields.iter().zip(packs.iter_mut()) {
-                let value = if self.ident_case_sensitive {
-                    &json[name]
-                } else {
-                    &json[name.to_lowercase()]
-                };
-                deser.de_json(value, &self.builder.format).map_err(|e| {
-                    let value_str = format!("{:?}", value);
-                    ErrorCode::BadBytes(format!(
-                        "error at row {} column {}: type={}, err={}, value={}",
-                        rows,
-                        name,
-                        type_name,
-                        e.message(),
-                        maybe_truncated(&value_str, 1024),
-                    ))
-                })?;
+                for ((name, type_name), deser) in fields.iter().zip(packs.iter_mut()) {
+                    let value = if ident_case_sensitive {
+                        &json[name]
+                    } else {
+                        &json[name.to_lowercase()]
+                    };
+
+                    // Under `arbitrary_precision`, hand the deserializer the number's
+                    // lossless textual representation instead of letting serde_json
+                    // coerce it to f64/i64. Captured before `value` is reassigned below,
+                    // since that reassignment turns it into a `Value::String` and
+                    // `value.is_number()` would otherwise always read false afterwards.
+                    let is_lossless_number = arbitrary_precision && value.is_number();
+
+                    let lossless;
+                    let value = match (arbitrary_precision, value) {
+                        (true, serde_json::Value::Number(n)) => {
+                            lossless = serde_json::Value::String(n.as_str().to_string());
+                            &lossless
+                        }
+                        _ => value,
+                    };
+
+                    deser
+                        .de_json(value, &self.builder.format)
+                        .map_err(|e| {
+                            let value_str = format!("{:?}", value);
+                            let hint = if is_lossless_number {
+                                " (number could not be represented losslessly in the target type)"
+                            } else {
+                                ""
+                            };
+                            ErrorCode::BadBytes(format!(
+                                "error at row {} column {}: type={}, err={}, value={}{}",
+                                rows,
+                                name,
+                                type_name,
+                                e.message(),
+                                maybe_truncated(&value_str, 1024),
+                                hint,
+                            ))
+                        })?;
+                }
+
+                Ok(Some(()))
+            })();
+
+            match line_result {
+                Ok(None) => break,
+                Ok(Some(())) => {}
+                Err(e) => match self.builder.on_error {
+                    NDJsonErrorPolicy::Abort => return Err(e),
+                    NDJsonErrorPolicy::SkipInvalid => {
+                        self.rejected_rows += 1;
+                        self.dead_letters.push((self.buffer.clone(), e.message()));
+                        continue;
+                    }
+                    NDJsonErrorPolicy::MaxErrors(n) => {
+                        self.rejected_rows += 1;
+                        self.dead_letters.push((self.buffer.clone(), e.message()));
+                        if self.rejected_rows > n {
+                            return Err(e);
+                        }
+                        continue;
+                    }
+                },
             }
 
             rows += 1;
@@ -202,4 +673,59 @@ where R: AsyncBufRead + Unpin + Send
 
         Ok(Some(DataBlock::create(self.builder.schema.clone(), series)))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use common_datavalues::DataField;
+    use common_datavalues::DataSchemaRefExt;
+    use futures::io::Cursor;
+
+    use super::*;
+
+    fn schema() -> DataSchemaRef {
+        DataSchemaRefExt::create(vec![DataField::new("a", DataType::String, false)])
+    }
+
+    #[test]
+    fn depth_limit_reader_rejects_nesting_past_max_depth() {
+        let mut reader = DepthLimitReader::new(&b"[[[1]]]"[..], 2);
+        let mut buf = Vec::new();
+        let err = StdRead::read_to_end(&mut reader, &mut buf).unwrap_err();
+        assert!(err.to_string().contains("exceeds the configured max_depth"));
+    }
+
+    #[test]
+    fn depth_limit_reader_ignores_brackets_inside_strings() {
+        let input: &[u8] = br#"{"a": "{[\"x\"]}"}"#;
+        let mut reader = DepthLimitReader::new(input, 1);
+        let mut buf = Vec::new();
+        StdRead::read_to_end(&mut reader, &mut buf).unwrap();
+        assert_eq!(buf, input);
+    }
+
+    #[test]
+    fn array_layout_recovers_from_a_bad_record_across_block_size_boundaries() {
+        let input: &[u8] = br#"[{"a": "1"}, not-json, {"a": "2"}, {"a": "3"}]"#;
+        let mut builder = NDJsonSourceBuilder::create(schema(), FormatSettings::default());
+        builder
+            .layout(JsonLayout::Array)
+            .block_size(1)
+            .on_error(NDJsonErrorPolicy::SkipInvalid);
+        let mut source = builder.build(Cursor::new(input.to_vec())).unwrap();
+
+        let mut blocks = 0;
+        while block_on(source.read()).unwrap().is_some() {
+            blocks += 1;
+        }
+
+        // 3 good records at block_size 1 means 3 separate blocks, despite the bad record
+        // landing between them - if Array mode got stuck after the first error the way a
+        // single long-lived StreamDeserializer does, everything after it would silently
+        // vanish and this would be 1.
+        assert_eq!(blocks, 3);
+        assert_eq!(source.rejected_rows(), 1);
+        assert_eq!(source.dead_letters().len(), 1);
+        assert!(source.dead_letters()[0].0.contains("not-json"));
+    }
+}